@@ -8,14 +8,33 @@
 //!
 //! This allows multiple GLTF scenes to share a single material for shader effects
 //! while maintaining individual hitboxes for interaction.
+//!
+//! Scene post-processing is built on top of a generic [`SceneHook`] subsystem:
+//! any entity carrying a `SceneHook` gets its closure invoked for every descendant
+//! spawned once the scene reports ready, with read access to the descendant's
+//! existing components and the ability to queue up arbitrary commands on it. The
+//! material replacement above is just the built-in hook this module registers.
+
+use std::collections::HashMap;
 
 use bevy::{
-    ecs::{lifecycle::HookContext, world::DeferredWorld},
+    ecs::{
+        lifecycle::HookContext,
+        reflect::{ReflectCommandExt, ReflectComponent},
+        world::DeferredWorld,
+    },
+    gltf::GltfExtras,
+    picking::mesh_picking::MeshPickingSettings,
     prelude::*,
+    reflect::ReflectDeserialize,
     scene::SceneInstanceReady,
 };
+use serde::Deserialize;
 
-use crate::{SharedHandles, shaders::interaction::InteractMaterial};
+use crate::{
+    SharedHandles,
+    shaders::interaction::{InteractMaterial, clone_per_instance_material},
+};
 
 /* === Plugin === */
 
@@ -23,12 +42,51 @@ pub struct InteractionPlugin;
 
 impl Plugin for InteractionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_observer(upgrade_interaction_materials);
+        // `MeshPickingPlugin` is the ray-casting backend that actually fires
+        // `Pointer<Over>/<Out>/<Click>` for `Pickable` meshes; `DefaultPlugins`
+        // doesn't include it, and this plugin owns the observers that consume
+        // those events, so it's registered here rather than in `main`. GLTF
+        // meshes aren't individually marked `Pickable`, only the sink hitbox
+        // is (via `#[require(Pickable::default())]`), so `require_markers` is
+        // turned off to let raycasts hit the rendered scene meshes too.
+        app.add_plugins(MeshPickingPlugin)
+            .insert_resource(MeshPickingSettings {
+                require_markers: false,
+                ..default()
+            })
+            .register_type::<InteractionSink>()
+            .add_message::<InteractionSinkClicked>()
+            .add_observer(run_scene_hooks)
+            .add_observer(hover_sink)
+            .add_observer(unhover_sink)
+            .add_observer(click_sink);
     }
 }
 
 /* === Definitions === */
 
+/// A per-entity hook invoked for every descendant of a scene once it is ready
+///
+/// Attach this to the same entity as a `SceneRoot` (or anything else observed
+/// via `SceneInstanceReady`) to run arbitrary logic over every entity the scene
+/// spawned: inspect what's already there through the read-only `EntityRef` and
+/// queue up inserts/removals/spawns through the paired `EntityCommands`. This is
+/// the extension point that lets consumers attach physics colliders, gameplay
+/// metadata, or LOD markers to freshly-loaded GLTF nodes without forking the
+/// crate.
+#[derive(Component)]
+pub struct SceneHook(Box<dyn Fn(&EntityRef, &mut EntityCommands) + Send + Sync>);
+
+impl SceneHook {
+    /// Wraps a closure as a `SceneHook`
+    pub fn new<F>(hook: F) -> Self
+    where
+        F: Fn(&EntityRef, &mut EntityCommands) + Send + Sync + 'static,
+    {
+        Self(Box::new(hook))
+    }
+}
+
 /// A hitbox entity that acts as the interaction point for a group of objects
 ///
 /// When added to an entity, the `on_add` hook (`setup_interaction_sink`) creates
@@ -36,57 +94,175 @@ impl Plugin for InteractionPlugin {
 /// Multiple GLTF scenes can reference the same sink, and they will all share
 /// this material handle for unified shader effects. When the shared palette
 /// material is loaded/modified, it is propagated to all InteractMaterial assets.
-#[derive(Component, Reflect, Default)]
+///
+/// `#[require(Pickable::default())]` makes the sink (and anything that
+/// forwards pointer events to it) hoverable/clickable; `hovered` is toggled by
+/// the pointer observers below and factored into the highlight intensity
+/// alongside camera proximity.
+///
+/// `#[reflect(Component, Deserialize)]` plus the `Deserialize` derive let GLTF
+/// extras construct a sink by name (see `insert_reflected_extras`) - so an
+/// artist can tag a mesh `InteractionSink` in Blender and have it picked up
+/// without any code change. `material` and `linked_materials` are skipped
+/// since they're populated by `setup_interaction_sink`/`setup_interactive_scene`,
+/// not authored by hand.
+#[derive(Component, Reflect, Default, Deserialize)]
+#[reflect(Component, Deserialize)]
 #[component(on_add = setup_interaction_sink)]
 #[require(Pickable::default())]
 pub struct InteractionSink {
+    #[serde(skip)]
     pub material: Handle<InteractMaterial>,
+    #[serde(default)]
+    pub hovered: bool,
+    /// Every `InteractMaterial` asset rendered by a scene linked to this sink
+    ///
+    /// Includes the shared handle for `MaterialMode::Shared` scenes and one
+    /// entry per `MaterialMode::PerInstance` clone, so `animate_highlight_intensity`
+    /// can drive proximity/hover highlighting on all of them, not just `material`.
+    #[serde(skip)]
+    pub linked_materials: Vec<Handle<InteractMaterial>>,
 }
 
 /// Links a GLTF scene to an InteractionSink
 ///
 /// When the scene is ready (SceneInstanceReady event), all StandardMaterial
 /// components in the scene's hierarchy are replaced with InteractMaterial
-/// components using the sink's material handle.
-#[derive(Component)]
+/// components using the sink's material handle (or, with `MaterialMode::PerInstance`,
+/// a reflection-cloned copy of it). This is implemented by attaching a
+/// built-in [`SceneHook`] to the scene entity via `on_add`, see
+/// `setup_interactive_scene`.
+#[derive(Component, Clone, Copy)]
+#[component(on_add = setup_interactive_scene)]
 pub struct InteractiveScene {
     pub sink: Entity,
+    pub material_mode: MaterialMode,
+}
+
+/// Whether scenes linked to a sink share one material asset or get their own
+///
+/// `Shared` (the default) is the original behaviour: every scene linked to an
+/// `InteractionSink` reuses the same `InteractMaterial` handle, so shader
+/// effects driven by the sink (highlight, proximity) stay in sync across every
+/// instance. `PerInstance` clones a fresh asset per scene root via
+/// `clone_per_instance_material`, so each instance of the same blueprint can be
+/// recolored independently once the caller has the returned handle.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum MaterialMode {
+    #[default]
+    Shared,
+    PerInstance,
 }
 
+/// Fired when an `InteractionSink` (or a scene linked to one) is clicked
+#[derive(Message, Clone, Copy)]
+pub struct InteractionSinkClicked(pub Entity);
+
 /* === Triggers === */
 
-/// Replaces StandardMaterials with InteractMaterials when scene is ready
+/// Raises `hovered` on the sink a pointer-over hit resolves to
+///
+/// Picked entities are deep GLTF children, not the sink itself, so the hit is
+/// resolved up to its owning `InteractionSink` via `resolve_sink` first. Since
+/// one sink's material is shared across every linked scene, hovering any
+/// descendant lights the whole group.
+fn hover_sink(
+    on: On<Pointer<Over>>,
+    mut q_interaction_sink: Query<&mut InteractionSink>,
+    q_interactive_scene: Query<&InteractiveScene>,
+    q_child_of: Query<&ChildOf>,
+) {
+    let Some(sink) = resolve_sink(on.entity, &q_interaction_sink, &q_interactive_scene, &q_child_of)
+    else {
+        return;
+    };
+
+    if let Ok(mut sink) = q_interaction_sink.get_mut(sink) {
+        sink.hovered = true;
+    }
+}
+
+/// Lowers `hovered` on the sink a pointer-out hit resolves to
+fn unhover_sink(
+    on: On<Pointer<Out>>,
+    mut q_interaction_sink: Query<&mut InteractionSink>,
+    q_interactive_scene: Query<&InteractiveScene>,
+    q_child_of: Query<&ChildOf>,
+) {
+    let Some(sink) = resolve_sink(on.entity, &q_interaction_sink, &q_interactive_scene, &q_child_of)
+    else {
+        return;
+    };
+
+    if let Ok(mut sink) = q_interaction_sink.get_mut(sink) {
+        sink.hovered = false;
+    }
+}
+
+/// Emits an `InteractionSinkClicked` for the sink a pointer-click hit resolves to
+fn click_sink(
+    on: On<Pointer<Click>>,
+    q_interaction_sink: Query<&mut InteractionSink>,
+    q_interactive_scene: Query<&InteractiveScene>,
+    q_child_of: Query<&ChildOf>,
+    mut clicked: MessageWriter<InteractionSinkClicked>,
+) {
+    let Some(sink) = resolve_sink(on.entity, &q_interaction_sink, &q_interactive_scene, &q_child_of)
+    else {
+        return;
+    };
+
+    clicked.write(InteractionSinkClicked(sink));
+}
+
+/// Resolves a picked entity up to its owning `InteractionSink`
+///
+/// The hit entity may be the sink itself, or a descendant of a `SceneRoot`
+/// linked to it via `InteractiveScene` several levels down the hierarchy. Walk
+/// up through `ChildOf` until either is found.
+fn resolve_sink(
+    entity: Entity,
+    q_interaction_sink: &Query<&mut InteractionSink>,
+    q_interactive_scene: &Query<&InteractiveScene>,
+    q_child_of: &Query<&ChildOf>,
+) -> Option<Entity> {
+    let mut current = entity;
+
+    loop {
+        if q_interaction_sink.contains(current) {
+            return Some(current);
+        }
+
+        if let Ok(scene) = q_interactive_scene.get(current) {
+            return Some(scene.sink);
+        }
+
+        current = q_child_of.get(current).ok()?.parent();
+    }
+}
+
+/// Runs every entity's `SceneHook` over its descendants when the scene is ready
 ///
-/// This observer responds to SceneInstanceReady events. When a scene with an
-/// InteractiveScene component finishes loading:
-/// 1. Gets the linked InteractionSink entity
-/// 2. Iterates through all descendants of the scene
-/// 3. Replaces the StandardMaterial with the shared InteractMaterial from the sink
-fn upgrade_interaction_materials(
+/// This observer responds to SceneInstanceReady events. For any entity that
+/// fired the event and carries a `SceneHook`, the hook closure is invoked for
+/// every descendant spawned by the scene, with a read-only `EntityRef` view of
+/// that descendant and `EntityCommands` to mutate it.
+fn run_scene_hooks(
     on: On<SceneInstanceReady>,
+    world: &World,
     mut commands: Commands,
     q_children: Query<&Children>,
-    q_interaction_sink: Query<&InteractionSink>,
-    q_scene_of: Query<&InteractiveScene>,
-    q_standard_material: Query<&MeshMaterial3d<StandardMaterial>>,
+    q_hooks: Query<&SceneHook>,
 ) {
-    // Get the related InteractionSink for this SceneRoot
-    let Ok(interaction_sink) = q_scene_of
-        .get(on.entity)
-        .and_then(|scene_of| q_interaction_sink.get(scene_of.sink))
-    else {
+    let Ok(hook) = q_hooks.get(on.entity) else {
         return;
     };
 
     // Iterate over the SceneRoot children
     for child in q_children.iter_descendants(on.entity) {
-        // Replace StandardMaterial with InteractMaterial from the sink
-        if q_standard_material.contains(child) {
-            commands
-                .entity(child)
-                .remove::<MeshMaterial3d<StandardMaterial>>()
-                .insert(MeshMaterial3d(interaction_sink.material.clone()));
-        }
+        let entity_ref = world.entity(child);
+        let mut entity_commands = commands.entity(child);
+        (hook.0)(&entity_ref, &mut entity_commands);
     }
 }
 
@@ -112,10 +288,127 @@ fn setup_interaction_sink(mut world: DeferredWorld, context: HookContext) {
         .resource_mut::<Assets<InteractMaterial>>()
         .add(InteractMaterial { base, ..default() });
 
-    // Replace the default dummy UUID handle in the InteractionSink
-    world
-        .entity_mut(context.entity)
-        .get_mut::<InteractionSink>()
-        .unwrap()
-        .material = material_handle;
+    // Replace the default dummy UUID handle in the InteractionSink, and seed
+    // `linked_materials` with it so `animate_highlight_intensity` has
+    // something to animate even before any scene attaches.
+    let mut sink_entity = world.entity_mut(context.entity);
+    let mut sink = sink_entity.get_mut::<InteractionSink>().unwrap();
+    sink.material = material_handle.clone();
+    sink.linked_materials.push(material_handle);
+}
+
+/// Component hook that attaches the built-in scene-upgrade `SceneHook`
+///
+/// Resolves the linked `InteractionSink`'s material handle and wraps it,
+/// together with GLTF extras resolution, in a single `SceneHook`: every
+/// descendant gets its `StandardMaterial` swapped for the sink's shared
+/// `InteractMaterial` (or, with `MaterialMode::PerInstance`, one clone of it
+/// made for this scene), and any `GltfExtras` it carries are wired up into real
+/// components. Attached here so both behave as built-in `SceneHook` consumers
+/// rather than bespoke logic callers would otherwise have to duplicate.
+fn setup_interactive_scene(mut world: DeferredWorld, context: HookContext) {
+    let scene = *world.get::<InteractiveScene>(context.entity).unwrap();
+
+    let Some(sink_material) = world
+        .get::<InteractionSink>(scene.sink)
+        .map(|sink| sink.material.clone())
+    else {
+        return;
+    };
+
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+
+    let material = match scene.material_mode {
+        MaterialMode::Shared => sink_material,
+        MaterialMode::PerInstance => {
+            let mut materials = world.resource_mut::<Assets<InteractMaterial>>();
+            clone_per_instance_material(&sink_material, &mut materials, &type_registry)
+        }
+    };
+
+    // Track the handle on the sink so `animate_highlight_intensity` can reach
+    // it too, not just the sink's own shared `material` - otherwise a
+    // `PerInstance` clone would be stranded at whatever highlight/time values
+    // it was cloned with and never animate again.
+    if let Some(mut sink) = world.get_mut::<InteractionSink>(scene.sink)
+        && !sink.linked_materials.contains(&material)
+    {
+        sink.linked_materials.push(material.clone());
+    }
+
+    world.commands().entity(context.entity).insert(SceneHook::new(
+        move |entity_ref, entity_commands| {
+            if entity_ref.contains::<MeshMaterial3d<StandardMaterial>>() {
+                entity_commands
+                    .remove::<MeshMaterial3d<StandardMaterial>>()
+                    .insert(MeshMaterial3d(material.clone()));
+            }
+
+            insert_reflected_extras(entity_ref, entity_commands, &type_registry);
+        },
+    ));
+}
+
+/* === GLTF Extras === */
+
+/// Deserializes an entity's GLTF `extras` into real components via reflection
+///
+/// Treats the `GltfExtras` JSON string as a map of `{ "TypeName": <value> }`.
+/// Each `TypeName` is looked up in the `AppTypeRegistry` for its
+/// `ReflectDeserialize` data, which is used to deserialize the paired JSON
+/// value into a reflected component that is then queued for insertion via
+/// `ReflectCommandExt::insert_reflect`. This is how Blender-authored custom
+/// properties (e.g. `InteractionSink`, a material group id) get wired onto
+/// GLTF nodes automatically instead of requiring code to hand-spawn them.
+/// `ReflectComponent` is also required before calling `insert_reflect`, since
+/// that call panics on a type that's registered but isn't a component (e.g.
+/// one registered only for `ReflectDeserialize`). Unregistered, non-component,
+/// or malformed entries are logged and skipped rather than causing a panic,
+/// since extras are authored outside the crate.
+fn insert_reflected_extras(
+    entity_ref: &EntityRef,
+    entity_commands: &mut EntityCommands,
+    type_registry: &AppTypeRegistry,
+) {
+    let Some(extras) = entity_ref.get::<GltfExtras>() else {
+        return;
+    };
+
+    let Ok(fields) = serde_json::from_str::<HashMap<String, serde_json::Value>>(&extras.value)
+    else {
+        warn!("GLTF extras are not a JSON object, skipping: {}", extras.value);
+        return;
+    };
+
+    let registry = type_registry.read();
+
+    for (type_name, value) in fields {
+        let Some(registration) = registry
+            .get_with_short_type_path(&type_name)
+            .or_else(|| registry.get_with_type_path(&type_name))
+        else {
+            warn!("GLTF extras referenced unregistered type `{type_name}`, skipping");
+            continue;
+        };
+
+        let Some(reflect_deserialize) = registration.data::<ReflectDeserialize>() else {
+            warn!("Type `{type_name}` has no ReflectDeserialize, skipping");
+            continue;
+        };
+
+        if registration.data::<ReflectComponent>().is_none() {
+            warn!("Type `{type_name}` has no ReflectComponent, skipping");
+            continue;
+        }
+
+        let deserializer = serde_json::Deserializer::from_str(&value.to_string());
+        match reflect_deserialize.deserialize(deserializer) {
+            Ok(reflected) => {
+                entity_commands.insert_reflect(reflected);
+            }
+            Err(error) => {
+                warn!("Failed to deserialize GLTF extras for `{type_name}`: {error}");
+            }
+        }
+    }
 }
@@ -0,0 +1,162 @@
+//! Level management: swaps GLTF levels via axis-aligned trigger zones
+//!
+//! Generalizes the one-shot `spawn_tiles` demo into a system that can manage
+//! multiple levels, each with its own `InteractionSink` group, swapping one
+//! out for another when a tracked entity (the camera) enters a level's
+//! trigger volume.
+//!
+//! Spawning is split across two system sets, `LevelSet::Spawn` and
+//! `LevelSet::AfterSpawn`. `Spawn` despawns the outgoing level and queues the
+//! incoming one; Bevy inserts an automatic sync point between chained sets, so
+//! those despawns are applied before `AfterSpawn` spawns the incoming sink and
+//! then its `SceneRoot`. The sink's commands are queued first, so the sink -
+//! and the `InteractMaterial` handle its `on_add` hook creates - exist before
+//! the `SceneRoot` is inserted and long before it fires its
+//! `SceneInstanceReady` observer. This sidesteps the same PostUpdate ordering hazard this crate
+//! reproduces, instead of stumbling into a same-frame variant of it.
+
+use bevy::{
+    math::{Vec3A, bounding::Aabb3d},
+    prelude::*,
+};
+
+use crate::interaction::{InteractionSink, InteractiveScene, MaterialMode};
+
+/* === Plugin === */
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveLevel>()
+            .init_resource::<PendingLevel>()
+            .configure_sets(Update, (LevelSet::Spawn, LevelSet::AfterSpawn).chain())
+            .add_systems(Update, check_level_triggers.in_set(LevelSet::Spawn))
+            .add_systems(Update, spawn_level_entities.in_set(LevelSet::AfterSpawn));
+    }
+}
+
+/* === Definitions === */
+
+/// System sets that sequence a level swap's spawn across the automatic sync point
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LevelSet {
+    /// Despawns the outgoing level and queues the incoming one in `PendingLevel`
+    Spawn,
+    /// Spawns the incoming level's sink and `SceneRoot` from `PendingLevel`
+    AfterSpawn,
+}
+
+/// An axis-aligned trigger volume that swaps in `target` when entered
+///
+/// Attach this anywhere; `check_level_triggers` watches every `LevelTransition`
+/// against the camera's translation each frame and, on entry, despawns the
+/// current level's `SceneRoot`/`InteractionSink` and spawns `target` with a
+/// fresh sink group linked via `InteractiveScene`.
+#[derive(Component)]
+pub struct LevelTransition {
+    pub target: Handle<Scene>,
+    pub zone: Aabb3d,
+}
+
+/// Tracks the currently active level's root entities so the next transition
+/// knows what to despawn before spawning its replacement
+///
+/// `target` records which `LevelTransition::target` is currently active, so
+/// `check_level_triggers` only swaps when a zone for a different level is
+/// entered.
+#[derive(Resource, Default)]
+struct ActiveLevel {
+    target: Option<Handle<Scene>>,
+    sink: Option<Entity>,
+    scene_root: Option<Entity>,
+}
+
+/// Scene handle queued by `check_level_triggers` for `spawn_level_entities` to
+/// spawn once the sync point between `LevelSet::Spawn` and `LevelSet::AfterSpawn`
+/// has applied the outgoing level's despawn commands
+#[derive(Resource, Default)]
+struct PendingLevel(Option<Handle<Scene>>);
+
+/* === Systems === */
+
+/// Despawns the active level and queues its replacement on zone entry
+///
+/// Only triggers for a level that isn't already active: if `active_level.target`
+/// already matches the zone's `target`, nothing happens, instead of respawning
+/// the level every frame its zone is occupied. Leaving the active level's zone
+/// and re-entering it therefore does not respawn that level either.
+fn check_level_triggers(
+    mut commands: Commands,
+    mut active_level: ResMut<ActiveLevel>,
+    mut pending_level: ResMut<PendingLevel>,
+    q_tracked: Query<&GlobalTransform, With<Camera>>,
+    q_transitions: Query<&LevelTransition>,
+) {
+    let Some(tracked_transform) = q_tracked.iter().next() else {
+        return;
+    };
+
+    let position = tracked_transform.translation();
+
+    for transition in &q_transitions {
+        if !aabb_contains_point(&transition.zone, position) {
+            continue;
+        }
+
+        if active_level.target.as_ref() == Some(&transition.target) {
+            break;
+        }
+
+        if let Some(sink) = active_level.sink.take() {
+            commands.entity(sink).despawn();
+        }
+
+        if let Some(scene_root) = active_level.scene_root.take() {
+            commands.entity(scene_root).despawn();
+        }
+
+        active_level.target = Some(transition.target.clone());
+        pending_level.0 = Some(transition.target.clone());
+        break;
+    }
+}
+
+/// Spawns the sink and `SceneRoot` for a level queued by `check_level_triggers`
+///
+/// Spawning the sink first and the scene second, in this dedicated set, mirrors
+/// `spawn_tiles` but guarantees the sink (and its material handle) are fully
+/// set up before `InteractiveScene`'s `on_add` hook - attached when the scene
+/// entity is spawned - tries to read them.
+fn spawn_level_entities(
+    mut commands: Commands,
+    mut active_level: ResMut<ActiveLevel>,
+    mut pending_level: ResMut<PendingLevel>,
+) {
+    let Some(target) = pending_level.0.take() else {
+        return;
+    };
+
+    let sink = commands.spawn(InteractionSink::default()).id();
+
+    let scene_root = commands
+        .spawn((
+            SceneRoot(target),
+            InteractiveScene {
+                sink,
+                material_mode: MaterialMode::Shared,
+            },
+        ))
+        .id();
+
+    active_level.sink = Some(sink);
+    active_level.scene_root = Some(scene_root);
+}
+
+/* === Utilities === */
+
+/// Whether `point` falls within `aabb`'s axis-aligned bounds
+fn aabb_contains_point(aabb: &Aabb3d, point: Vec3) -> bool {
+    let point = Vec3A::from(point);
+    (aabb.min.cmple(point) & aabb.max.cmpge(point)).all()
+}
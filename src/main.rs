@@ -18,9 +18,10 @@ use bevy::{
 use bevy_inspector_egui::bevy_egui::EguiPlugin;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 
-use crate::interaction::{InteractionSink, InteractiveScene};
+use crate::interaction::{InteractionSink, InteractiveScene, MaterialMode};
 
 mod interaction;
+mod level;
 mod shaders;
 
 /* === Entrypoint === */
@@ -34,6 +35,7 @@ pub fn main() {
         WorldInspectorPlugin::new(),
         shaders::ShadersPlugin,
         interaction::InteractionPlugin,
+        level::LevelPlugin,
     ))
     .add_systems(Startup, setup_scene)
     .add_systems(Update, move_camera)
@@ -161,7 +163,10 @@ fn spawn_tiles(
     commands.spawn((
         SceneRoot(scene.clone()),
         Transform::from_translation(MapTransform::to_vec3(Vec2::new(64., 0.))),
-        InteractiveScene { sink },
+        InteractiveScene {
+            sink,
+            material_mode: MaterialMode::Shared,
+        },
     ));
 
     info!("Scene ready! Press Space to start moving the camera.");
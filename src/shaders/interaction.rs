@@ -4,19 +4,27 @@
 //! All InteractMaterials in a group share the same base StandardMaterial,
 //! which allows synchronized shader effects across multiple objects.
 
+use std::any::TypeId;
+
 use bevy::{
     pbr::{ExtendedMaterial, MaterialExtension},
     prelude::*,
-    render::render_resource::AsBindGroup,
+    reflect::{ReflectFromReflect, TypePath, TypeRegistry},
+    render::render_resource::{AsBindGroup, ShaderRef},
 };
 
+use crate::interaction::InteractionSink;
+
 /* === Plugin === */
 
 pub struct InteractShaderPlugin;
 
 impl Plugin for InteractShaderPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(MaterialPlugin::<InteractMaterial>::default());
+        app.register_type::<StandardMaterial>()
+            .register_type::<InteractMaterialExt>()
+            .add_plugins(MaterialPlugin::<InteractMaterial>::default())
+            .add_systems(Update, animate_highlight_intensity);
         // .add_systems(
         //     PostUpdate,
         //     create_interact_material.after(AssetEventSystems),
@@ -30,14 +38,151 @@ impl Plugin for InteractShaderPlugin {
 /// having access to custom shader extensions for special effects.
 pub type InteractMaterial = ExtendedMaterial<StandardMaterial, InteractMaterialExt>;
 
-/// Uses the PBR fallback shader for demonstration purposes.
-#[derive(Asset, AsBindGroup, Reflect, Clone, Default)]
-pub struct InteractMaterialExt {}
+/// Proximity-lit highlight extension for interactive objects
+///
+/// `highlight_intensity` is driven by `animate_highlight_intensity` based on
+/// distance between the camera and the owning `InteractionSink`, fading the
+/// highlight in as the camera approaches. `highlight_color` is the tint mixed
+/// into the base color/emissive, and `time` is exposed for effects that need
+/// animation independent of proximity (e.g. a pulse).
+#[derive(Asset, AsBindGroup, Reflect, Clone)]
+pub struct InteractMaterialExt {
+    #[uniform(100)]
+    pub highlight_color: LinearRgba,
+    #[uniform(100)]
+    pub highlight_intensity: f32,
+    #[uniform(100)]
+    pub time: f32,
+}
 
-impl MaterialExtension for InteractMaterialExt {}
+impl Default for InteractMaterialExt {
+    fn default() -> Self {
+        Self {
+            highlight_color: LinearRgba::rgb(1.0, 0.8, 0.2),
+            highlight_intensity: 0.0,
+            time: 0.0,
+        }
+    }
+}
+
+impl MaterialExtension for InteractMaterialExt {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/interact_extension.wgsl".into()
+    }
+}
 
 /* === Systems === */
 
+/// Distance at which an `InteractionSink`'s highlight starts fading in
+///
+/// Intensity falls off linearly from full at distance 0 to zero at this range.
+const PROXIMITY_RANGE: f32 = 30.0;
+
+/// Fades each sink's highlight in as the camera approaches its transform
+///
+/// Writes into every `InteractMaterial` asset linked to the sink (its own
+/// shared handle plus any `MaterialMode::PerInstance` clones tracked in
+/// `linked_materials`), so every scene linked to the `InteractionSink`
+/// highlights together, like a proximity light, regardless of which material
+/// mode spawned it. The pointer-driven `hovered` flag (see the observers in
+/// `interaction.rs`) is folded in here too, so hovering any linked mesh
+/// overrides proximity and lights the whole group at full intensity.
+fn animate_highlight_intensity(
+    time: Res<Time>,
+    q_camera: Query<&GlobalTransform, With<Camera>>,
+    q_sinks: Query<(&InteractionSink, &GlobalTransform)>,
+    mut materials: ResMut<Assets<InteractMaterial>>,
+) {
+    let Some(camera_transform) = q_camera.iter().next() else {
+        return;
+    };
+
+    for (sink, sink_transform) in &q_sinks {
+        let distance = camera_transform
+            .translation()
+            .distance(sink_transform.translation());
+
+        let proximity = (1.0 - distance / PROXIMITY_RANGE).clamp(0.0, 1.0);
+        let hovered = if sink.hovered { 1.0 } else { 0.0 };
+        let intensity = proximity.max(hovered);
+
+        for handle in &sink.linked_materials {
+            let Some(material) = materials.get_mut(handle) else {
+                continue;
+            };
+
+            material.extension.highlight_intensity = intensity;
+            material.extension.time = time.elapsed_secs();
+        }
+    }
+}
+
+/* === Reflection === */
+
+/// Clones an `ExtendedMaterial` asset via reflection for `MaterialMode::PerInstance`
+///
+/// Both the `base` and `extension` fields are copied through
+/// `reflect_clone_registered`, which looks up `ReflectFromReflect` for each
+/// field's concrete type in the `AppTypeRegistry` rather than calling
+/// `.clone()` directly. This is generic over the extension type `E`, the same
+/// way a `CloneEntity`-style command is generic over whatever components it's
+/// given, so a second `MaterialExtension` works here without touching this
+/// function - it only needs its own `app.register_type::<E>()` call. Panics
+/// if `StandardMaterial` or `E` isn't registered, since silently falling back
+/// to the shared handle would defeat the point of asking for a per-instance
+/// material.
+pub fn clone_per_instance_material<E>(
+    source: &Handle<ExtendedMaterial<StandardMaterial, E>>,
+    materials: &mut Assets<ExtendedMaterial<StandardMaterial, E>>,
+    type_registry: &AppTypeRegistry,
+) -> Handle<ExtendedMaterial<StandardMaterial, E>>
+where
+    E: MaterialExtension + Reflect + TypePath + Clone,
+{
+    let source_material = materials
+        .get(source)
+        .expect("source material asset must exist for per-instance cloning");
+
+    let registry = type_registry.read();
+
+    let base = reflect_clone_registered(&source_material.base, &registry);
+    let extension = reflect_clone_registered(&source_material.extension, &registry);
+
+    materials.add(ExtendedMaterial { base, extension })
+}
+
+/// Reflection-clones a single value via its `AppTypeRegistry` registration
+///
+/// Looks up `T`'s `TypeId` in the registry, copies `value` through
+/// `PartialReflect::clone_value`, and reconstructs a concrete `T` via the
+/// registered `ReflectFromReflect`. Panics rather than silently falling back
+/// to a shared reference if `T` isn't registered or doesn't support
+/// `FromReflect`, since either would produce a value that looks independent
+/// but secretly isn't.
+fn reflect_clone_registered<T: Reflect + TypePath>(value: &T, registry: &TypeRegistry) -> T {
+    let registration = registry.get(TypeId::of::<T>()).unwrap_or_else(|| {
+        panic!(
+            "{} must be registered in the AppTypeRegistry for per-instance cloning",
+            T::type_path()
+        )
+    });
+
+    let reflect_from_reflect = registration.data::<ReflectFromReflect>().unwrap_or_else(|| {
+        panic!(
+            "{} must support FromReflect for per-instance cloning",
+            T::type_path()
+        )
+    });
+
+    let cloned_value = value.clone_value();
+
+    *reflect_from_reflect
+        .from_reflect(cloned_value.as_ref())
+        .unwrap_or_else(|| panic!("failed to reconstruct {} from its cloned reflection data", T::type_path()))
+        .downcast::<T>()
+        .unwrap_or_else(|_| panic!("ReflectFromReflect produced an unexpected concrete type for {}", T::type_path()))
+}
+
 // Synchronizes all InteractMaterials when the shared palette material changes
 //
 // Listens for AssetEvent::Added or AssetEvent::Modified on the palette material